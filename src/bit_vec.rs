@@ -1,35 +1,47 @@
 use alloc::vec::Vec;
 
-/// A minimal growable bit vector backed by a byte array.
-/// Provides constant-time access and mutation of individual bits.
+/// A fixed-size bit vector backed by an array of 64-bit words.
+///
+/// The bit count is always rounded up to the next power of two, so a bit index
+/// can be reduced into range with a single mask (`index & (m - 1)`) instead of
+/// a modulo. This keeps the hot path free of 64-bit division and removes the
+/// modulo bias that a non-power-of-two length would introduce.
 #[derive(Debug, Clone)]
 pub(crate) struct BitVec {
-    bits: Vec<u8>,
+    words: Vec<u64>,
+    mask: usize,
 }
 
-const BITS_PER_BYTE: usize = 8;
+const BITS_PER_WORD: usize = 64;
 
 impl BitVec {
-    /// Creates a new `BitVec` with enough space for `bytes` bytes (i.e., `bytes * 8` bits).
+    /// Creates a new `BitVec` with at least `min_bytes` bytes of capacity.
     ///
-    /// All bits are initialized to 0.
+    /// The requested size (in bits) is rounded up to the next power of two, and
+    /// all bits are initialized to 0.
     ///
     /// # Arguments
     ///
-    /// * `bytes` - The number of bytes to allocate internally.
+    /// * `min_bytes` - The minimum number of bytes of capacity required.
     #[inline]
-    pub fn new(bytes: usize) -> Self {
+    pub fn new(min_bytes: usize) -> Self {
+        let min_bits = min_bytes * 8;
+        let nbits = min_bits.next_power_of_two();
+        let nwords = nbits.div_ceil(BITS_PER_WORD).max(1);
+
         Self {
-            bits: vec![0u8; bytes],
+            words: vec![0u64; nwords],
+            mask: nbits - 1,
         }
     }
 
-    /// Returns the total number of **bytes** in the internal storage.
+    /// Reduces a raw hash into an in-range bit index with a mask.
     ///
-    /// Note: To get total number of **bits**, multiply this by 8.
+    /// Because the capacity is a power of two, `hash & (m - 1)` is equivalent to
+    /// `hash % m` but avoids the division.
     #[inline]
-    pub fn len(&self) -> usize {
-        self.bits.len()
+    pub fn bit_index(&self, hash: u64) -> usize {
+        (hash as usize) & self.mask
     }
 
     /// Checks if the bit at the given offset is set.
@@ -43,12 +55,12 @@ impl BitVec {
     /// Panics if `bit_offset` is out of bounds.
     #[inline]
     pub fn contain(&self, bit_offset: usize) -> bool {
-        let byte_offset = bit_offset / BITS_PER_BYTE;
-        let bit_shift = bit_offset % BITS_PER_BYTE;
+        let word_offset = bit_offset / BITS_PER_WORD;
+        let bit_shift = bit_offset % BITS_PER_WORD;
 
-        debug_assert!(byte_offset < self.bits.len(), "bit_offset out of bounds");
+        debug_assert!(word_offset < self.words.len(), "bit_offset out of bounds");
 
-        (self.bits()[byte_offset] & (1 << bit_shift)) != 0
+        (self.words[word_offset] & (1u64 << bit_shift)) != 0
     }
 
     /// Sets the bit at the given offset to `1`.
@@ -62,37 +74,94 @@ impl BitVec {
     /// Panics if `bit_offset` is out of bounds.
     #[inline]
     pub fn set(&mut self, bit_offset: usize) {
-        let byte_offset = bit_offset / BITS_PER_BYTE;
-        let bit_shift = bit_offset % BITS_PER_BYTE;
+        let word_offset = bit_offset / BITS_PER_WORD;
+        let bit_shift = bit_offset % BITS_PER_WORD;
 
-        debug_assert!(byte_offset < self.bits.len(), "bit_offset out of bounds");
+        debug_assert!(word_offset < self.words.len(), "bit_offset out of bounds");
 
-        self.bits_mut()[byte_offset] |= 1 << bit_shift;
+        self.words[word_offset] |= 1u64 << bit_shift;
     }
 
-    /// Returns a read-only view of the internal byte array.
+    /// Serializes the backing words into a little-endian byte buffer.
     #[inline]
-    fn bits(&self) -> &[u8] {
-        &self.bits
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.words.len() * 8);
+        for word in &self.words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reconstructs a `BitVec` of `nbits` bits from its little-endian byte form.
+    ///
+    /// Returns `None` if `nbits` is not a positive power of two, or if `bytes`
+    /// does not contain exactly eight bytes per stored word.
+    pub fn from_parts(nbits: usize, bytes: &[u8]) -> Option<Self> {
+        if nbits == 0 || !nbits.is_power_of_two() {
+            return None;
+        }
+
+        let nwords = nbits.div_ceil(BITS_PER_WORD).max(1);
+        if bytes.len() != nwords * 8 {
+            return None;
+        }
+
+        let mut words = Vec::with_capacity(nwords);
+        for chunk in bytes.chunks_exact(8) {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(chunk);
+            words.push(u64::from_le_bytes(buf));
+        }
+
+        Some(Self {
+            words,
+            mask: nbits - 1,
+        })
+    }
+
+    /// ORs each word of `other` into `self` in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two bit vectors differ in length.
+    #[inline]
+    pub fn union_with(&mut self, other: &BitVec) {
+        assert_eq!(self.words.len(), other.words.len(), "length mismatch");
+        for (dst, src) in self.words.iter_mut().zip(other.words.iter()) {
+            *dst |= *src;
+        }
+    }
+
+    /// ANDs each word of `other` into `self` in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two bit vectors differ in length.
+    #[inline]
+    pub fn intersect_with(&mut self, other: &BitVec) {
+        assert_eq!(self.words.len(), other.words.len(), "length mismatch");
+        for (dst, src) in self.words.iter_mut().zip(other.words.iter()) {
+            *dst &= *src;
+        }
     }
 
-    /// Returns a mutable view of the internal byte array.
+    /// Returns the number of bits currently set to `1` across the whole vector.
     #[inline]
-    fn bits_mut(&mut self) -> &mut [u8] {
-        &mut self.bits
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
     }
 
-    /// Returns the total number of bits in the bit vector (`len() * 8`).
+    /// Returns the total number of bits in the bit vector (always a power of two).
     #[inline]
     pub fn capacity_in_bits(&self) -> usize {
-        self.len() * BITS_PER_BYTE
+        self.mask + 1
     }
 
     /// Resets all bits to 0.
     #[inline]
     pub fn clear(&mut self) {
-        for byte in &mut self.bits {
-            *byte = 0;
+        for word in &mut self.words {
+            *word = 0;
         }
     }
 }
@@ -1,87 +1,61 @@
-use core::{
-    f64::consts::LN_2,
-    hash::{Hash, Hasher},
-};
+use core::{f64::consts::LN_2, hash::Hash};
 
+use alloc::vec::Vec;
 use libm::{ceil, log, log2, pow};
 use rand_core::RngCore;
-use siphasher::sip::SipHasher13;
 
 use crate::bit_vec::BitVec;
+use crate::error::Error;
+use crate::hasher::{BloomHasher, SipDoubleHasher};
+
+/// Number of header bytes preceding the raw bit-vector bytes in the serialized
+/// form: one `u64` for `hash_fn_number`, four `u64`s for the two SipHash key
+/// pairs, and one `u64` for the bit-vector bit length.
+const SERIALIZED_HEADER_LEN: usize = 8 * 6;
 
 /// A probabilistic Bloom filter for membership testing with configurable
 /// false positive rate and no false negatives.
 ///
-/// This implementation uses double hashing based on two SipHash-1-3 hashers
-/// seeded with independent keys for reproducibility and security.
+/// The filter is generic over a [`BloomHasher`] that decides how items map to
+/// bit positions. The default, [`SipDoubleHasher`], uses double hashing over
+/// two SipHash-1-3 instances seeded with independent keys for reproducibility
+/// and security; callers may substitute a faster hasher via
+/// [`with_hasher`](Bloom::with_hasher).
 ///
 /// The bit vector size and number of hash functions are calculated based on
 /// the expected number of items and desired false positive rate.
 #[derive(Debug, Clone)]
-pub struct Bloom {
+pub struct Bloom<H = SipDoubleHasher> {
     bits: BitVec,
     hash_fn_number: usize,
-    hashers: [SipHasher13; 2],
+    hasher: H,
 }
 
-impl Bloom {
-    /// Creates a new Bloom filter with the specified expected number of items,
-    /// desired false positive rate, and explicit SipHash keys.
+impl<H> Bloom<H>
+where
+    H: BloomHasher,
+{
+    /// Creates a new Bloom filter sized for the expected number of items and
+    /// desired false positive rate, using the supplied hasher.
     ///
-    /// # Arguments
-    ///
-    /// * `items` - Expected number of items to be inserted (must be > 0).
-    /// * `err_rate` - Desired false positive probability (0 < err_rate < 1).
-    /// * `keys` - Array of two `(u64, u64)` tuples used as keys for SipHash.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `items` is zero or if `err_rate` is not in (0,1).
-    pub fn new_with_key(items: usize, err_rate: f64, keys: [(u64, u64); 2]) -> Self {
-        let bits_size = Self::calculate_bits_vec_size(items, err_rate);
-        let hash_fn_number = Self::calculate_hash_fn_number(err_rate);
-        let [key0, key1] = keys;
-
-        let hashers = [
-            SipHasher13::new_with_keys(key0.0, key0.1),
-            SipHasher13::new_with_keys(key1.0, key1.1),
-        ];
-
-        Self {
-            bits: BitVec::new(bits_size),
-            hash_fn_number,
-            hashers,
-        }
-    }
-
-    /// Creates a new Bloom filter with the specified expected number of items,
-    /// false positive rate, and a random number generator to seed SipHash keys.
+    /// This is the generic entry point behind [`new_with_key`](Bloom::new_with_key)
+    /// and [`new_with_rng`](Bloom::new_with_rng); use it to plug in a custom
+    /// [`BloomHasher`].
     ///
     /// # Arguments
     ///
     /// * `items` - Expected number of items to be inserted (must be > 0).
     /// * `err_rate` - Desired false positive probability (0 < err_rate < 1).
-    /// * `rng` - Mutable reference to a random number generator implementing `RngCore`.
+    /// * `hasher` - The hashing strategy used to derive bit positions.
     ///
     /// # Panics
     ///
     /// Panics if `items` is zero or if `err_rate` is not in (0,1).
-    pub fn new_with_rng<R: RngCore>(items: usize, err_rate: f64, rng: &mut R) -> Self {
-        let hash_fn_number = Self::calculate_hash_fn_number(err_rate);
-        let keys = [
-            (rng.next_u64(), rng.next_u64()),
-            (rng.next_u64(), rng.next_u64()),
-        ];
-
-        let hashers = [
-            SipHasher13::new_with_keys(keys[0].0, keys[0].1),
-            SipHasher13::new_with_keys(keys[1].0, keys[1].1),
-        ];
-
+    pub fn with_hasher(items: usize, err_rate: f64, hasher: H) -> Self {
         Self {
-            bits: BitVec::new(Self::calculate_bits_vec_size(items, err_rate)),
-            hash_fn_number,
-            hashers,
+            bits: BitVec::new(calculate_bits_vec_size(items, err_rate)),
+            hash_fn_number: calculate_hash_fn_number(err_rate),
+            hasher,
         }
     }
 
@@ -94,9 +68,9 @@ impl Bloom {
     where
         T: Hash,
     {
-        let (h1, h2) = self.bloom_hash(item);
+        let hashes = self.hasher.hashes(item);
         for i in 0..self.hash_fn_number {
-            let index = self.get_index((h1, h2), i as u64);
+            let index = self.get_index(&hashes, i as u64);
             self.bits.set(index);
         }
     }
@@ -112,9 +86,9 @@ impl Bloom {
     where
         T: Hash,
     {
-        let (h1, h2) = self.bloom_hash(item);
+        let hashes = self.hasher.hashes(item);
         for i in 0..self.hash_fn_number {
-            let index = self.get_index((h1, h2), i as u64);
+            let index = self.get_index(&hashes, i as u64);
             if !self.bits.contain(index) {
                 return false;
             }
@@ -122,82 +96,262 @@ impl Bloom {
         true
     }
 
-    /// Hashes an item into two base hash values using the internal SipHash instances.
+    /// Computes the bit index for the `i`th hash function by masking the
+    /// combined hash into the power-of-two bit vector.
+    #[inline]
+    fn get_index(&self, hashes: &H::Hashes, i: u64) -> usize {
+        self.bits.bit_index(self.hasher.combine(hashes, i))
+    }
+
+    /// Returns the total capacity of the Bloom filter in **bits**.
     ///
-    /// This is used to implement double hashing for generating multiple hash values.
+    /// The underlying bit vector rounds its size up to the next power of two,
+    /// so this is always a power of two.
     #[inline]
-    fn bloom_hash<T>(&self, item: &T) -> (u64, u64)
-    where
-        T: Hash,
-    {
-        let mut hasher1 = self.hashers[0];
-        let mut hasher2 = self.hashers[1];
+    pub fn capacity_in_bits(&self) -> usize {
+        self.bits.capacity_in_bits()
+    }
 
-        item.hash(&mut hasher1);
-        item.hash(&mut hasher2);
+    /// Clears all bits in the Bloom filter, effectively resetting it.
+    ///
+    /// After calling this, the filter will behave as if it's empty.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
 
-        (hasher1.finish(), hasher2.finish())
+    /// Estimates the number of distinct items that have been inserted, from the
+    /// current fill level of the bit vector.
+    ///
+    /// Uses the standard estimator `n ≈ -(m/k) * ln(1 - X/m)`, where `m` is the
+    /// bit-vector size, `k` the number of hash functions, and `X` the number of
+    /// set bits. The result is `0.0` for an empty filter and grows without
+    /// bound as the filter saturates (`X → m`), so callers can use it to detect
+    /// when a filter should be rebuilt with larger parameters.
+    pub fn estimated_item_count(&self) -> f64 {
+        let m = self.bits.capacity_in_bits() as f64;
+        let x = self.bits.count_ones() as f64;
+        let k = self.hash_fn_number as f64;
+
+        -(m / k) * log(1.0 - x / m)
     }
 
-    /// Computes the bit index for the `i`th hash function using double hashing:
+    /// Estimates the current expected false-positive probability, `(X/m)^k`,
+    /// where `X` is the number of set bits, `m` the bit-vector size, and `k` the
+    /// number of hash functions.
     ///
-    /// `g_i(x) = (h1(x) + i * h2(x)) mod m` where `m` is the bit vector size.
-    #[inline]
-    fn get_index(&self, (h1, h2): (u64, u64), i: u64) -> usize {
-        let len = self.bits.len() as u64;
-        (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize
+    /// Unlike the target rate fixed at construction, this reflects how full the
+    /// filter actually is and rises as more items are inserted.
+    pub fn estimated_fp_rate(&self) -> f64 {
+        let m = self.bits.capacity_in_bits() as f64;
+        let x = self.bits.count_ones() as f64;
+        let k = self.hash_fn_number as f64;
+
+        pow(x / m, k)
+    }
+}
+
+impl Bloom<SipDoubleHasher> {
+    /// Creates a new Bloom filter with the specified expected number of items,
+    /// desired false positive rate, and explicit SipHash keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - Expected number of items to be inserted (must be > 0).
+    /// * `err_rate` - Desired false positive probability (0 < err_rate < 1).
+    /// * `keys` - Array of two `(u64, u64)` tuples used as keys for SipHash.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is zero or if `err_rate` is not in (0,1).
+    pub fn new_with_key(items: usize, err_rate: f64, keys: [(u64, u64); 2]) -> Self {
+        Self::with_hasher(items, err_rate, SipDoubleHasher::new(keys))
     }
 
-    /// Calculates the minimum size of the bit vector (in bytes) needed to achieve
-    /// the specified false positive rate given the expected number of items.
+    /// Creates a new Bloom filter with the specified expected number of items,
+    /// false positive rate, and a random number generator to seed SipHash keys.
     ///
-    /// Formula used:
-    /// ```text
-    /// m = - (n * ln ε) / (8 * (ln 2)^2)
-    /// ```
+    /// # Arguments
     ///
-    /// where `n` is number of items, `ε` is false positive rate, and `m` is bit vector size in bytes.
+    /// * `items` - Expected number of items to be inserted (must be > 0).
+    /// * `err_rate` - Desired false positive probability (0 < err_rate < 1).
+    /// * `rng` - Mutable reference to a random number generator implementing `RngCore`.
     ///
     /// # Panics
     ///
-    /// Panics if `items == 0` or `fp_rate` not in `(0,1)`.
+    /// Panics if `items` is zero or if `err_rate` is not in (0,1).
+    pub fn new_with_rng<R: RngCore>(items: usize, err_rate: f64, rng: &mut R) -> Self {
+        let keys = [
+            (rng.next_u64(), rng.next_u64()),
+            (rng.next_u64(), rng.next_u64()),
+        ];
+
+        Self::with_hasher(items, err_rate, SipDoubleHasher::new(keys))
+    }
+
+    /// Returns `true` if `other` shares the parameters required to combine it
+    /// with `self`: identical bit-vector length, number of hash functions, and
+    /// SipHash keys. Only compatible filters address bits the same way, so
+    /// merging incompatible ones would silently break the membership guarantee.
     #[inline]
-    fn calculate_bits_vec_size(items: usize, fp_rate: f64) -> usize {
-        assert!(items > 0, "Number of items must be > 0");
-        assert!(
-            (0.0..1.0).contains(&fp_rate),
-            "False positive rate must be between 0 and 1"
-        );
+    fn is_compatible(&self, other: &Self) -> bool {
+        self.bits.capacity_in_bits() == other.bits.capacity_in_bits()
+            && self.hash_fn_number == other.hash_fn_number
+            && self.hasher == other.hasher
+    }
 
-        ceil(-((items as f64 * log(fp_rate)) / (pow(LN_2, 2.0) * 8.0))) as usize
+    /// ORs `other` into `self` in place, so the result reports membership for
+    /// every item in either filter. Useful for merging sharded filters built in
+    /// parallel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IncompatibleFilters`] if the two filters differ in
+    /// bit-vector length, number of hash functions, or SipHash keys.
+    pub fn union_with(&mut self, other: &Self) -> Result<(), Error> {
+        if !self.is_compatible(other) {
+            return Err(Error::IncompatibleFilters);
+        }
+        self.bits.union_with(&other.bits);
+        Ok(())
     }
 
-    /// Calculates the optimal number of hash functions needed for the given false positive rate.
+    /// ANDs `other` into `self` in place, so the result reports membership only
+    /// for bits set in both filters.
     ///
-    /// Formula:
-    /// ```text
-    /// k = ceil(-log_2(ε))
-    /// ```
+    /// # Errors
     ///
-    /// where `ε` is false positive rate, and `k` is number of hash functions.
-    #[inline]
-    fn calculate_hash_fn_number(fp_rate: f64) -> usize {
-        ceil(-log2(fp_rate)) as usize
+    /// Returns [`Error::IncompatibleFilters`] if the two filters differ in
+    /// bit-vector length, number of hash functions, or SipHash keys.
+    pub fn intersect_with(&mut self, other: &Self) -> Result<(), Error> {
+        if !self.is_compatible(other) {
+            return Err(Error::IncompatibleFilters);
+        }
+        self.bits.intersect_with(&other.bits);
+        Ok(())
     }
 
-    /// Returns the total capacity of the Bloom filter in **bits**.
+    /// Returns a new filter that is the union of `self` and `other`, leaving
+    /// both operands untouched.
     ///
-    /// This is equal to the number of bytes in the underlying bit vector multiplied by 8.
-    #[inline]
-    pub fn capacity_in_bits(&self) -> usize {
-        self.bits.capacity_in_bits()
+    /// # Errors
+    ///
+    /// Returns [`Error::IncompatibleFilters`] if the two filters differ in
+    /// bit-vector length, number of hash functions, or SipHash keys.
+    pub fn union(&self, other: &Self) -> Result<Self, Error> {
+        let mut merged = self.clone();
+        merged.union_with(other)?;
+        Ok(merged)
     }
 
-    /// Clears all bits in the Bloom filter, effectively resetting it.
+    /// Returns a new filter that is the intersection of `self` and `other`,
+    /// leaving both operands untouched.
     ///
-    /// After calling this, the filter will behave as if it's empty.
-    #[inline]
-    pub fn clear(&mut self) {
-        self.bits.clear();
+    /// # Errors
+    ///
+    /// Returns [`Error::IncompatibleFilters`] if the two filters differ in
+    /// bit-vector length, number of hash functions, or SipHash keys.
+    pub fn intersect(&self, other: &Self) -> Result<Self, Error> {
+        let mut merged = self.clone();
+        merged.intersect_with(other)?;
+        Ok(merged)
+    }
+
+    /// Serializes the filter into a byte buffer that can be persisted and later
+    /// rebuilt with [`from_bytes`](Bloom::from_bytes).
+    ///
+    /// The encoding is a fixed-size little-endian header — `hash_fn_number`,
+    /// the two SipHash key pairs, and the bit-vector bit length — followed by
+    /// the raw bit-vector bytes. Because the crate is `#![no_std]` the result
+    /// is an [`alloc::vec::Vec<u8>`] rather than something tied to `std::io`,
+    /// so callers can round-trip a populated filter through their own storage
+    /// layer without re-inserting every item.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let raw = self.bits.as_bytes();
+        let mut buf = Vec::with_capacity(SERIALIZED_HEADER_LEN + raw.len());
+
+        buf.extend_from_slice(&(self.hash_fn_number as u64).to_le_bytes());
+        for (lo, hi) in self.hasher.keys().iter() {
+            buf.extend_from_slice(&lo.to_le_bytes());
+            buf.extend_from_slice(&hi.to_le_bytes());
+        }
+        buf.extend_from_slice(&(self.bits.capacity_in_bits() as u64).to_le_bytes());
+        buf.extend_from_slice(&raw);
+
+        buf
     }
+
+    /// Reconstructs a filter from bytes produced by [`to_bytes`](Bloom::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedBytes`] if the buffer is shorter than the
+    /// fixed header, if the declared bit-vector length is zero or not a power
+    /// of two, or if it is inconsistent with the number of trailing bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < SERIALIZED_HEADER_LEN {
+            return Err(Error::MalformedBytes);
+        }
+
+        let hash_fn_number = read_u64(&bytes[0..8]) as usize;
+        let keys = [
+            (read_u64(&bytes[8..16]), read_u64(&bytes[16..24])),
+            (read_u64(&bytes[24..32]), read_u64(&bytes[32..40])),
+        ];
+        let nbits = read_u64(&bytes[40..48]) as usize;
+
+        let raw = &bytes[SERIALIZED_HEADER_LEN..];
+        let bits = BitVec::from_parts(nbits, raw).ok_or(Error::MalformedBytes)?;
+
+        Ok(Self {
+            bits,
+            hash_fn_number,
+            hasher: SipDoubleHasher::new(keys),
+        })
+    }
+}
+
+/// Calculates the minimum size of the bit vector (in bytes) needed to achieve
+/// the specified false positive rate given the expected number of items.
+///
+/// Formula used:
+/// ```text
+/// m = - (n * ln ε) / (8 * (ln 2)^2)
+/// ```
+///
+/// where `n` is number of items, `ε` is false positive rate, and `m` is bit vector size in bytes.
+///
+/// # Panics
+///
+/// Panics if `items == 0` or `fp_rate` not in `(0,1)`.
+#[inline]
+pub(crate) fn calculate_bits_vec_size(items: usize, fp_rate: f64) -> usize {
+    assert!(items > 0, "Number of items must be > 0");
+    assert!(
+        (0.0..1.0).contains(&fp_rate),
+        "False positive rate must be between 0 and 1"
+    );
+
+    ceil(-((items as f64 * log(fp_rate)) / (pow(LN_2, 2.0) * 8.0))) as usize
+}
+
+/// Calculates the optimal number of hash functions needed for the given false positive rate.
+///
+/// Formula:
+/// ```text
+/// k = ceil(-log_2(ε))
+/// ```
+///
+/// where `ε` is false positive rate, and `k` is number of hash functions.
+#[inline]
+pub(crate) fn calculate_hash_fn_number(fp_rate: f64) -> usize {
+    ceil(-log2(fp_rate)) as usize
+}
+
+/// Reads a little-endian `u64` from an 8-byte slice.
+#[inline]
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
 }
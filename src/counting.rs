@@ -0,0 +1,341 @@
+use core::hash::{Hash, Hasher};
+
+use rand_core::RngCore;
+use siphasher::sip::SipHasher13;
+
+use crate::bloom::{calculate_bits_vec_size, calculate_hash_fn_number};
+
+/// Width (in bits) of each saturating counter in a [`CountingBloom`].
+///
+/// Narrower counters use less memory at the cost of saturating sooner; an
+/// 8-bit counter tolerates far more overlapping insertions before pinning to
+/// its maximum than a 4-bit one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterWidth {
+    /// 4-bit counters packed two-per-byte, saturating at 15.
+    Four,
+    /// 8-bit counters, saturating at 255.
+    Eight,
+}
+
+impl CounterWidth {
+    /// Largest value a counter of this width can hold before saturating.
+    #[inline]
+    fn max(self) -> u8 {
+        match self {
+            CounterWidth::Four => 0x0F,
+            CounterWidth::Eight => 0xFF,
+        }
+    }
+
+    /// Number of bits occupied by a single counter of this width.
+    #[inline]
+    fn bits(self) -> usize {
+        match self {
+            CounterWidth::Four => 4,
+            CounterWidth::Eight => 8,
+        }
+    }
+}
+
+/// A packed array of small saturating counters.
+///
+/// Unlike a plain bit vector each slot records how many times it has been
+/// touched, which is what lets a counting filter support removal. Increments
+/// and decrements saturate at the counter's maximum: once a counter pins to
+/// its ceiling it stays there, so an overflowed slot is never decremented back
+/// down into a range that could produce false negatives.
+#[derive(Debug, Clone)]
+struct Counters {
+    data: alloc::vec::Vec<u8>,
+    width: CounterWidth,
+    len: usize,
+}
+
+impl Counters {
+    /// Allocates `len` zeroed counters of the given `width`.
+    fn new(len: usize, width: CounterWidth) -> Self {
+        let bytes = match width {
+            CounterWidth::Four => len.div_ceil(2),
+            CounterWidth::Eight => len,
+        };
+
+        Self {
+            data: vec![0u8; bytes],
+            width,
+            len,
+        }
+    }
+
+    /// Reads the counter at `index`.
+    #[inline]
+    fn get(&self, index: usize) -> u8 {
+        match self.width {
+            CounterWidth::Four => {
+                let byte = self.data[index / 2];
+                if index.is_multiple_of(2) {
+                    byte & 0x0F
+                } else {
+                    byte >> 4
+                }
+            }
+            CounterWidth::Eight => self.data[index],
+        }
+    }
+
+    /// Writes `value` into the counter at `index`.
+    #[inline]
+    fn put(&mut self, index: usize, value: u8) {
+        match self.width {
+            CounterWidth::Four => {
+                let byte = &mut self.data[index / 2];
+                if index.is_multiple_of(2) {
+                    *byte = (*byte & 0xF0) | (value & 0x0F);
+                } else {
+                    *byte = (*byte & 0x0F) | (value << 4);
+                }
+            }
+            CounterWidth::Eight => self.data[index] = value,
+        }
+    }
+
+    /// Increments the counter at `index`, saturating at its maximum.
+    #[inline]
+    fn increment(&mut self, index: usize) {
+        let current = self.get(index);
+        if current < self.width.max() {
+            self.put(index, current + 1);
+        }
+    }
+
+    /// Decrements the counter at `index`, unless it is zero or already
+    /// saturated. Saturated counters are left pinned to avoid false negatives.
+    #[inline]
+    fn decrement(&mut self, index: usize) {
+        let current = self.get(index);
+        if current != 0 && current < self.width.max() {
+            self.put(index, current - 1);
+        }
+    }
+
+    /// Number of counter slots.
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Total number of bits occupied by all counters.
+    #[inline]
+    fn len_in_bits(&self) -> usize {
+        self.len * self.width.bits()
+    }
+
+    /// Resets every counter to zero.
+    #[inline]
+    fn clear(&mut self) {
+        for byte in &mut self.data {
+            *byte = 0;
+        }
+    }
+}
+
+/// A counting Bloom filter that supports removal.
+///
+/// This mirrors [`Bloom`](crate::Bloom) but replaces the single-bit backing with an array of
+/// small saturating counters, as Servo's ancestor filter does. [`insert`]
+/// increments the `k` counters for an item, [`remove`] decrements them, and
+/// [`contain`] reports membership when all `k` counters are non-zero. Counters
+/// saturate so they never wrap on overflow, and once saturated they stay
+/// saturated to preserve the no-false-negatives guarantee.
+///
+/// [`insert`]: CountingBloom::insert
+/// [`remove`]: CountingBloom::remove
+/// [`contain`]: CountingBloom::contain
+#[derive(Debug, Clone)]
+pub struct CountingBloom {
+    counters: Counters,
+    hash_fn_number: usize,
+    hashers: [SipHasher13; 2],
+}
+
+impl CountingBloom {
+    /// Creates a new counting filter with 4-bit counters, explicit SipHash
+    /// keys, the expected number of items, and desired false positive rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - Expected number of items to be inserted (must be > 0).
+    /// * `err_rate` - Desired false positive probability (0 < err_rate < 1).
+    /// * `keys` - Array of two `(u64, u64)` tuples used as keys for SipHash.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is zero or if `err_rate` is not in (0,1).
+    pub fn new_with_key(items: usize, err_rate: f64, keys: [(u64, u64); 2]) -> Self {
+        Self::new_with_key_and_width(items, err_rate, keys, CounterWidth::Four)
+    }
+
+    /// Creates a new counting filter with explicit SipHash keys and an explicit
+    /// counter width.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is zero or if `err_rate` is not in (0,1).
+    pub fn new_with_key_and_width(
+        items: usize,
+        err_rate: f64,
+        keys: [(u64, u64); 2],
+        width: CounterWidth,
+    ) -> Self {
+        let counter_len = calculate_bits_vec_size(items, err_rate) * 8;
+        let hash_fn_number = calculate_hash_fn_number(err_rate);
+        let [key0, key1] = keys;
+
+        let hashers = [
+            SipHasher13::new_with_keys(key0.0, key0.1),
+            SipHasher13::new_with_keys(key1.0, key1.1),
+        ];
+
+        Self {
+            counters: Counters::new(counter_len, width),
+            hash_fn_number,
+            hashers,
+        }
+    }
+
+    /// Creates a new counting filter with 4-bit counters and a random number
+    /// generator to seed SipHash keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is zero or if `err_rate` is not in (0,1).
+    pub fn new_with_rng<R: RngCore>(items: usize, err_rate: f64, rng: &mut R) -> Self {
+        Self::new_with_rng_and_width(items, err_rate, rng, CounterWidth::Four)
+    }
+
+    /// Creates a new counting filter with an explicit counter width and a
+    /// random number generator to seed SipHash keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is zero or if `err_rate` is not in (0,1).
+    pub fn new_with_rng_and_width<R: RngCore>(
+        items: usize,
+        err_rate: f64,
+        rng: &mut R,
+        width: CounterWidth,
+    ) -> Self {
+        let counter_len = calculate_bits_vec_size(items, err_rate) * 8;
+        let hash_fn_number = calculate_hash_fn_number(err_rate);
+        let keys = [
+            (rng.next_u64(), rng.next_u64()),
+            (rng.next_u64(), rng.next_u64()),
+        ];
+
+        let hashers = [
+            SipHasher13::new_with_keys(keys[0].0, keys[0].1),
+            SipHasher13::new_with_keys(keys[1].0, keys[1].1),
+        ];
+
+        Self {
+            counters: Counters::new(counter_len, width),
+            hash_fn_number,
+            hashers,
+        }
+    }
+
+    /// Inserts an item, incrementing each of its `k` counters.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - A reference to the item to insert, which must implement `Hash`.
+    pub fn insert<T>(&mut self, item: &T)
+    where
+        T: Hash,
+    {
+        let (h1, h2) = self.bloom_hash(item);
+        for i in 0..self.hash_fn_number {
+            let index = self.get_index((h1, h2), i as u64);
+            self.counters.increment(index);
+        }
+    }
+
+    /// Removes an item, decrementing each of its `k` counters.
+    ///
+    /// Removing an item that was never inserted, or removing the same item more
+    /// times than it was inserted, may corrupt counts for other items; callers
+    /// must balance [`insert`](CountingBloom::insert) and `remove` as with any
+    /// counting filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - A reference to the item to remove, which must implement `Hash`.
+    pub fn remove<T>(&mut self, item: &T)
+    where
+        T: Hash,
+    {
+        let (h1, h2) = self.bloom_hash(item);
+        for i in 0..self.hash_fn_number {
+            let index = self.get_index((h1, h2), i as u64);
+            self.counters.decrement(index);
+        }
+    }
+
+    /// Checks if an item is possibly in the filter.
+    ///
+    /// Returns `true` when all `k` counters for the item are non-zero, or
+    /// `false` if the item is definitely not present.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - A reference to the item to check, which must implement `Hash`.
+    pub fn contain<T>(&self, item: &T) -> bool
+    where
+        T: Hash,
+    {
+        let (h1, h2) = self.bloom_hash(item);
+        for i in 0..self.hash_fn_number {
+            let index = self.get_index((h1, h2), i as u64);
+            if self.counters.get(index) == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Hashes an item into two base hash values using the internal SipHash
+    /// instances, used to drive double hashing.
+    #[inline]
+    fn bloom_hash<T>(&self, item: &T) -> (u64, u64)
+    where
+        T: Hash,
+    {
+        let mut hasher1 = self.hashers[0];
+        let mut hasher2 = self.hashers[1];
+
+        item.hash(&mut hasher1);
+        item.hash(&mut hasher2);
+
+        (hasher1.finish(), hasher2.finish())
+    }
+
+    /// Computes the counter index for the `i`th hash function using double
+    /// hashing: `g_i(x) = (h1(x) + i * h2(x)) mod m`.
+    #[inline]
+    fn get_index(&self, (h1, h2): (u64, u64), i: u64) -> usize {
+        let len = self.counters.len() as u64;
+        (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize
+    }
+
+    /// Returns the total number of bits occupied by the filter's counters.
+    #[inline]
+    pub fn capacity_in_bits(&self) -> usize {
+        self.counters.len_in_bits()
+    }
+
+    /// Clears all counters, effectively resetting the filter.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.counters.clear();
+    }
+}
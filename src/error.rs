@@ -0,0 +1,25 @@
+use core::fmt;
+
+/// Errors returned by the fallible operations on a [`Bloom`](crate::Bloom) filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A serialized buffer passed to [`Bloom::from_bytes`](crate::Bloom::from_bytes)
+    /// was truncated, or its declared bit-vector length was not a positive power
+    /// of two or did not match the number of trailing bytes.
+    MalformedBytes,
+    /// Two filters were combined (via union or intersection) but differ in a
+    /// parameter that must be identical: bit-vector length, number of hash
+    /// functions, or SipHash keys.
+    IncompatibleFilters,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MalformedBytes => f.write_str("malformed serialized bloom filter"),
+            Error::IncompatibleFilters => {
+                f.write_str("filters have incompatible parameters")
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use core::hash::{Hash, Hasher};
+
+use siphasher::sip::SipHasher13;
+
+/// Strategy for deriving the `k` bit positions a [`Bloom`](crate::Bloom) filter
+/// probes for an item.
+///
+/// Hashing is split into two steps so the expensive per-item work happens once:
+/// [`hashes`](BloomHasher::hashes) digests the item into reusable state, and
+/// [`combine`](BloomHasher::combine) cheaply derives the `index`th probe hash
+/// from that state. The filter reduces each combined value modulo the
+/// bit-vector size to pick a bit.
+///
+/// This follows Solana's `BloomHashIndex` design: the default
+/// [`SipDoubleHasher`] keeps the secure SipHash-1-3 double-hashing scheme —
+/// digesting the item through both SipHashers a single time — while callers
+/// handling trusted input can plug in a faster non-cryptographic hasher, or one
+/// that hashes pre-computed 64-bit fingerprints directly.
+pub trait BloomHasher {
+    /// Per-item state from which individual probe hashes are derived.
+    type Hashes;
+
+    /// Digests `item` into reusable hash state. Called once per insert or query.
+    fn hashes<T>(&self, item: &T) -> Self::Hashes
+    where
+        T: Hash;
+
+    /// Derives the `index`th probe hash from previously computed state. Called
+    /// once per hash function and must not re-digest the item.
+    fn combine(&self, hashes: &Self::Hashes, index: u64) -> u64;
+}
+
+/// The default hasher: double hashing over two independently keyed
+/// SipHash-1-3 instances.
+///
+/// [`hashes`](BloomHasher::hashes) digests the item once through both SipHashers
+/// to produce `(h1, h2)`, and [`combine`](BloomHasher::combine) derives the
+/// `index`th probe as `h1 + index * h2`. The keys are retained so a filter
+/// built with this hasher can be serialized and checked for compatibility.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SipDoubleHasher {
+    keys: [(u64, u64); 2],
+}
+
+impl SipDoubleHasher {
+    /// Creates a hasher seeded with the two given SipHash key pairs.
+    #[inline]
+    pub fn new(keys: [(u64, u64); 2]) -> Self {
+        Self { keys }
+    }
+
+    /// Returns the SipHash key pairs this hasher was seeded with.
+    #[inline]
+    pub(crate) fn keys(&self) -> [(u64, u64); 2] {
+        self.keys
+    }
+}
+
+impl BloomHasher for SipDoubleHasher {
+    type Hashes = (u64, u64);
+
+    #[inline]
+    fn hashes<T>(&self, item: &T) -> (u64, u64)
+    where
+        T: Hash,
+    {
+        let [key0, key1] = self.keys;
+        let mut hasher1 = SipHasher13::new_with_keys(key0.0, key0.1);
+        let mut hasher2 = SipHasher13::new_with_keys(key1.0, key1.1);
+
+        item.hash(&mut hasher1);
+        item.hash(&mut hasher2);
+
+        (hasher1.finish(), hasher2.finish())
+    }
+
+    #[inline]
+    fn combine(&self, &(h1, h2): &(u64, u64), index: u64) -> u64 {
+        h1.wrapping_add(index.wrapping_mul(h2))
+    }
+}
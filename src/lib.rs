@@ -5,5 +5,11 @@ extern crate alloc;
 
 mod bit_vec;
 mod bloom;
+mod counting;
+mod error;
+mod hasher;
 
 pub use crate::bloom::Bloom;
+pub use crate::counting::{CounterWidth, CountingBloom};
+pub use crate::error::Error;
+pub use crate::hasher::{BloomHasher, SipDoubleHasher};
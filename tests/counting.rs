@@ -0,0 +1,100 @@
+use bitbloom::{CounterWidth, CountingBloom};
+use rand_core::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+
+#[test]
+fn should_match_insert_and_query() {
+    let mut bloom = CountingBloom::new_with_key(100, 0.01, [(0, 1), (2, 3)]);
+
+    bloom.insert(&"hello");
+    bloom.insert(&"world");
+
+    assert!(bloom.contain(&"hello"));
+    assert!(bloom.contain(&"world"));
+}
+
+#[test]
+fn should_forget_item_after_remove() {
+    let mut bloom = CountingBloom::new_with_key(10_000, 0.01, [(0, 1), (2, 3)]);
+
+    bloom.insert(&"hello");
+    assert!(bloom.contain(&"hello"));
+
+    bloom.remove(&"hello");
+    assert!(!bloom.contain(&"hello"));
+}
+
+#[test]
+fn should_keep_item_inserted_twice_after_single_remove() {
+    let mut bloom = CountingBloom::new_with_key(10_000, 0.01, [(10, 20), (30, 40)]);
+
+    bloom.insert(&"dup");
+    bloom.insert(&"dup");
+    bloom.remove(&"dup");
+
+    assert!(bloom.contain(&"dup"));
+}
+
+#[test]
+fn should_saturate_without_wrapping() {
+    let mut bloom = CountingBloom::new_with_key(100, 0.01, [(1, 2), (3, 4)]);
+
+    // Insert far beyond a 4-bit counter's ceiling; the counters must pin at
+    // their maximum rather than wrap around to zero.
+    for _ in 0..1000 {
+        bloom.insert(&"saturate");
+    }
+    // A single removal of a saturated counter is a no-op, so membership holds.
+    bloom.remove(&"saturate");
+
+    assert!(bloom.contain(&"saturate"));
+}
+
+#[test]
+fn should_clear_all_counters() {
+    let mut bloom = CountingBloom::new_with_key(10_000, 0.01, [(10, 20), (30, 40)]);
+
+    bloom.insert(&"hello");
+    bloom.insert(&"world");
+
+    bloom.clear();
+
+    assert!(!bloom.contain(&"hello"));
+    assert!(!bloom.contain(&"world"));
+}
+
+#[test]
+fn should_support_eight_bit_counters() {
+    let mut rng = Pcg64Mcg::seed_from_u64(7);
+    let mut bloom =
+        CountingBloom::new_with_rng_and_width(10_000, 0.01, &mut rng, CounterWidth::Eight);
+
+    bloom.insert(&"foo");
+    bloom.insert(&"foo");
+    bloom.remove(&"foo");
+
+    assert!(bloom.contain(&"foo"));
+
+    bloom.remove(&"foo");
+    assert!(!bloom.contain(&"foo"));
+}
+
+#[test]
+fn should_keep_false_positive_rate_near_target() {
+    let mut bloom = CountingBloom::new_with_key(1000, 0.01, [(1, 2), (3, 4)]);
+
+    for i in 0..1000 {
+        bloom.insert(&i);
+    }
+
+    let mut false_positives = 0;
+    let trials = 10_000;
+    for i in 1000..1000 + trials {
+        if bloom.contain(&i) {
+            false_positives += 1;
+        }
+    }
+
+    let rate = false_positives as f64 / trials as f64;
+    assert!(rate < 0.05, "false-positive rate {} too high", rate);
+}
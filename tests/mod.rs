@@ -1,4 +1,4 @@
-use bitbloom::Bloom;
+use bitbloom::{Bloom, Error};
 use rand_core::SeedableRng;
 use rand_pcg::Pcg64Mcg;
 
@@ -76,3 +76,163 @@ fn should_clear_all_bits() {
     assert!(!bloom.contain(&"hello"));
     assert!(!bloom.contain(&"world"));
 }
+
+#[test]
+fn should_round_trip_through_bytes() {
+    let mut bloom = Bloom::new_with_key(1000, 0.01, [(7, 8), (9, 10)]);
+
+    let inserted = ["apple", "banana", "cherry", "date"];
+    for word in &inserted {
+        bloom.set(word);
+    }
+
+    let bytes = bloom.to_bytes();
+    let restored = Bloom::from_bytes(&bytes).expect("round-trip should succeed");
+
+    for word in &inserted {
+        assert!(restored.contain(word), "Item {:?} should survive serialization", word);
+    }
+    assert_eq!(restored.capacity_in_bits(), bloom.capacity_in_bits());
+}
+
+#[test]
+fn should_reject_truncated_bytes() {
+    let bloom = Bloom::new_with_key(100, 0.01, [(1, 2), (3, 4)]);
+    let mut bytes = bloom.to_bytes();
+    bytes.truncate(bytes.len() - 1);
+
+    assert!(matches!(
+        Bloom::from_bytes(&bytes),
+        Err(Error::MalformedBytes)
+    ));
+}
+
+#[test]
+fn should_reject_short_buffer() {
+    assert!(matches!(
+        Bloom::from_bytes(&[0u8; 4]),
+        Err(Error::MalformedBytes)
+    ));
+}
+
+#[test]
+fn should_union_compatible_filters() {
+    let mut a = Bloom::new_with_key(1000, 0.01, [(1, 2), (3, 4)]);
+    let mut b = Bloom::new_with_key(1000, 0.01, [(1, 2), (3, 4)]);
+
+    a.set(&"apple");
+    b.set(&"banana");
+
+    let merged = a.union(&b).expect("compatible filters should union");
+    assert!(merged.contain(&"apple"));
+    assert!(merged.contain(&"banana"));
+}
+
+#[test]
+fn should_intersect_compatible_filters() {
+    let mut a = Bloom::new_with_key(1000, 0.01, [(1, 2), (3, 4)]);
+    let mut b = Bloom::new_with_key(1000, 0.01, [(1, 2), (3, 4)]);
+
+    a.set(&"shared");
+    a.set(&"only_a");
+    b.set(&"shared");
+
+    let merged = a.intersect(&b).expect("compatible filters should intersect");
+    assert!(merged.contain(&"shared"));
+    assert!(!merged.contain(&"only_a"));
+}
+
+#[test]
+fn should_reject_incompatible_filters() {
+    let a = Bloom::new_with_key(1000, 0.01, [(1, 2), (3, 4)]);
+    let b = Bloom::new_with_key(1000, 0.01, [(5, 6), (7, 8)]);
+
+    assert!(matches!(a.union(&b), Err(Error::IncompatibleFilters)));
+}
+
+#[test]
+fn should_estimate_zero_items_when_empty() {
+    let bloom = Bloom::new_with_key(1000, 0.01, [(1, 2), (3, 4)]);
+
+    assert_eq!(bloom.estimated_item_count(), 0.0);
+    assert_eq!(bloom.estimated_fp_rate(), 0.0);
+}
+
+#[test]
+fn should_estimate_inserted_item_count() {
+    let mut rng = Pcg64Mcg::seed_from_u64(99);
+    let mut bloom = Bloom::new_with_rng(10_000, 0.001, &mut rng);
+
+    let n = 2_000;
+    for i in 0..n {
+        bloom.set(&i);
+    }
+
+    // The estimate should land within 10% of the true insertion count.
+    let estimate = bloom.estimated_item_count();
+    let error = (estimate - n as f64).abs() / n as f64;
+    assert!(error < 0.10, "estimate {} too far from {}", estimate, n);
+}
+
+#[test]
+fn should_report_rising_fp_rate_as_filter_fills() {
+    let mut rng = Pcg64Mcg::seed_from_u64(11);
+    let mut bloom = Bloom::new_with_rng(1000, 0.01, &mut rng);
+
+    for i in 0..100 {
+        bloom.set(&i);
+    }
+    let low = bloom.estimated_fp_rate();
+
+    for i in 100..2000 {
+        bloom.set(&i);
+    }
+    let high = bloom.estimated_fp_rate();
+
+    assert!(high > low);
+}
+
+/// A minimal non-cryptographic FNV-1a hasher, used to exercise the pluggable
+/// `BloomHasher` trait.
+struct FnvHasher(u64);
+
+impl core::hash::Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+struct Fnv;
+
+impl bitbloom::BloomHasher for Fnv {
+    type Hashes = u64;
+
+    fn hashes<T: core::hash::Hash>(&self, item: &T) -> u64 {
+        use core::hash::Hasher;
+        let mut h = FnvHasher(0xcbf2_9ce4_8422_2325);
+        item.hash(&mut h);
+        h.finish()
+    }
+
+    fn combine(&self, hashes: &u64, index: u64) -> u64 {
+        hashes.wrapping_add(index.wrapping_mul(0x0100_0000_01b3))
+    }
+}
+
+#[test]
+fn should_work_with_a_custom_hasher() {
+    let mut bloom = bitbloom::Bloom::with_hasher(1000, 0.01, Fnv);
+
+    bloom.set(&"hello");
+    bloom.set(&"world");
+
+    assert!(bloom.contain(&"hello"));
+    assert!(bloom.contain(&"world"));
+}